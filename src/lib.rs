@@ -0,0 +1,44 @@
+pub mod config;
+
+use std::fmt;
+
+/// Errors produced by the core config-processing and line-routing engine.
+///
+/// Library callers match on this instead of the process exiting out from
+/// under them; the `secern` binary is the only thing that turns these into
+/// log messages and an exit code.
+#[derive(Debug)]
+pub enum SecernError {
+    /// A config file couldn't be read or deserialized in the format it was
+    /// read as, its format couldn't be determined, or the merged configs
+    /// are invalid (an include cycle, or duplicate sink or output file
+    /// names).
+    ConfigParse(String),
+    /// One or more sinks had a pattern that failed to compile. Collected
+    /// across every sink in a config rather than stopping at the first.
+    RegexCompile(Vec<String>),
+    /// A sink's output destination (file, rotation archive, syslog
+    /// connection, including an invalid syslog facility), the input file,
+    /// or a converted or template config file couldn't be created, opened
+    /// or serialized.
+    FileCreate(String),
+    /// Writing or flushing a sink's output, or a converted or template
+    /// config file, failed. `broken_pipe`
+    /// distinguishes a downstream reader closing early (e.g. piping into
+    /// `head`), which callers typically want to treat as a clean stop
+    /// rather than a hard failure.
+    Write { context: String, broken_pipe: bool },
+}
+
+impl fmt::Display for SecernError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SecernError::ConfigParse(msg) => write!(f, "{msg}"),
+            SecernError::RegexCompile(errors) => write!(f, "{}", errors.join("; ")),
+            SecernError::FileCreate(msg) => write!(f, "{msg}"),
+            SecernError::Write { context, .. } => write!(f, "{context}"),
+        }
+    }
+}
+
+impl std::error::Error for SecernError {}
@@ -1,8 +1,5 @@
-mod config;
-
-use std::fs;
 use std::io::prelude::*;
-use std::io::{self, BufReader, BufWriter};
+use std::io::{self, BufWriter};
 
 use std::time::Instant;
 
@@ -11,30 +8,24 @@ use clap::{Arg, ArgAction, Command, crate_authors, crate_description, crate_name
 use env_logger::Env;
 use log::{error, info};
 
-// final_flush ensures that all buffered file output is written before bailing
-fn final_flush(
-    mut filters: Vec<config::FilterConfig>,
-    mut stdio_writer: std::io::BufWriter<std::io::Stdout>,
-) {
-    for filter in &mut filters {
-        match &mut filter.file {
-            None => (),
-            Some(out_file) => {
-                match out_file.flush() {
-                    Ok(_data) => (),
-                    Err(e) => {
-                        error!(
-                            "Error flushing final data to output file '{}' for sink named '{}' due to error: {}",
-                            filter.file_name, filter.name, e
-                        );
-                        std::process::exit(1);
-                    }
-                };
+use secern::SecernError;
+use secern::config;
+
+// exit_with_error logs `e` (reporting every collected pattern on a
+// RegexCompile error instead of just the joined summary) and exits with a
+// failure status. Callers that can tell a broken pipe apart from a real
+// failure should handle SecernError::Write { broken_pipe: true, .. }
+// themselves before reaching here.
+fn exit_with_error(e: SecernError) -> ! {
+    match &e {
+        SecernError::RegexCompile(errors) => {
+            for msg in errors {
+                error!("{msg}");
             }
         }
+        _ => error!("{e}"),
     }
-
-    stdio_writer.flush().unwrap();
+    std::process::exit(1);
 }
 
 fn main() {
@@ -47,8 +38,8 @@ fn main() {
                 .short('c')
                 .long("config")
                 .value_name("FILE")
-                .help("Specifies the YAML config file")
-                .action(ArgAction::Set),
+                .help("Specifies a config file (YAML/JSON/TOML); may be given multiple times to merge sinks from several files")
+                .action(ArgAction::Append),
         )
         .arg(
             Arg::new("generate")
@@ -79,6 +70,36 @@ fn main() {
                 .help("Disables emmitting info level log events (version, run time, etc) on STDERR")
                 .action(ArgAction::SetTrue),
         )
+        .arg(
+            Arg::new("input")
+                .long("input")
+                .value_name("FILE")
+                .help("Reads from FILE instead of STDIN, transparently decompressing .gz/.zst/.bz2")
+                .action(ArgAction::Set),
+        )
+        .arg(
+            Arg::new("format")
+                .long("format")
+                .value_name("FORMAT")
+                .help("Format to use with --gen-template: yaml, json, or toml (default: yaml)")
+                .action(ArgAction::Set),
+        )
+        .subcommand(
+            Command::new("convert-config")
+                .about("Converts a sink configuration file between YAML, JSON, and TOML")
+                .arg(
+                    Arg::new("input")
+                        .value_name("INPUT")
+                        .help("Path to the configuration file to convert")
+                        .required(true),
+                )
+                .arg(
+                    Arg::new("output")
+                        .value_name("OUTPUT")
+                        .help("Path to write the converted configuration file to")
+                        .required(true),
+                ),
+        )
         .get_matches();
 
     // Initialize logging
@@ -87,15 +108,36 @@ fn main() {
 
     info!("{} {}", crate_name!(), crate_version!());
 
+    if let Some(sub_matches) = matches.subcommand_matches("convert-config") {
+        let input = sub_matches.get_one::<String>("input").unwrap();
+        let output = sub_matches.get_one::<String>("output").unwrap();
+        match config::convert_config(input, output) {
+            Ok(()) => std::process::exit(0),
+            Err(e) => exit_with_error(e),
+        }
+    }
+
+    let format: config::ConfigFormat = match matches.get_one::<String>("format") {
+        Some(s) => match s.parse() {
+            Ok(f) => f,
+            Err(e) => {
+                error!("{e}");
+                std::process::exit(1);
+            }
+        },
+        None => config::ConfigFormat::Yaml,
+    };
+
     if let Some(t) = matches.get_one::<String>("generate") {
-        config::generate_config(t);
+        match config::generate_config(t, format) {
+            Ok(()) => std::process::exit(0),
+            Err(e) => exit_with_error(e),
+        }
     }
 
-    let config: &str = match matches.get_one::<String>("config") {
-        Some(s) => s,
+    let config_paths: Vec<String> = match matches.get_many::<String>("config") {
+        Some(values) => values.cloned().collect(),
         None => {
-            // clap ensures that the value of `config` is populated but handle
-            // missing values here anyway.
             error!("Please specify the configuration file!");
             std::process::exit(1)
         }
@@ -103,18 +145,16 @@ fn main() {
 
     let validate_only: bool = matches.get_flag("validate-only");
 
-    info!("Loading configuration file: {config}");
+    info!("Loading configuration file(s): {}", config_paths.join(", "));
 
-    let config_data = fs::read_to_string(config);
-    let config_data = match config_data {
-        Ok(data) => data,
-        Err(e) => {
-            error!("Unable to open specified configuration file ({config}) due to error: {e}");
-            std::process::exit(1);
-        }
+    let sinks = match config::load_sink_configs(&config_paths) {
+        Ok(sinks) => sinks,
+        Err(e) => exit_with_error(e),
+    };
+    let mut filters = match config::process_config(sinks, validate_only) {
+        Ok(filters) => filters,
+        Err(e) => exit_with_error(e),
     };
-
-    let mut filters = config::process_config(config, config_data, validate_only);
 
     if validate_only {
         info!("Configuration summary");
@@ -126,45 +166,21 @@ fn main() {
     let start = Instant::now();
     let mut stdio_writer = BufWriter::with_capacity(4096 * 1024, io::stdout());
 
-    let stdin = BufReader::with_capacity(64 * 1024, io::stdin());
+    let input = match config::open_input(matches.get_one::<String>("input").map(|s| s.as_str())) {
+        Ok(input) => input,
+        Err(e) => exit_with_error(e),
+    };
     let mut found_match: bool;
-    for entry in stdin.lines() {
+    for entry in input.lines() {
         let line = entry.unwrap();
         found_match = false;
 
         for filter in &mut filters {
-            let mut matched: bool = filter.regex_set.is_match(&line);
-            if filter.invert {
-                matched = !matched;
-            }
-            if matched {
-                match &mut filter.file {
-                    None => (),
-                    Some(out_file) => {
-                        match out_file.write_all(line.as_bytes()) {
-                            Ok(_) => (),
-                            Err(e) if e.kind() == std::io::ErrorKind::BrokenPipe => {
-                                std::process::exit(0);
-                            }
-                            Err(e) => {
-                                error!(
-                                    "Unable to write to output file '{}' for sink named '{}' due to error: {}",
-                                    filter.file_name, filter.name, e
-                                );
-                                std::process::exit(1);
-                            }
-                        };
-
-                        match out_file.write_all(b"\n") {
-                            Ok(_) => (),
-                            Err(e) => {
-                                error!(
-                                    "Unable to write to output file '{}' for sink named '{}' due to error: {}",
-                                    filter.file_name, filter.name, e
-                                );
-                                std::process::exit(1);
-                            }
-                        };
+            if config::matches(filter, &line) {
+                if let Err(e) = config::route_line(filter, &line) {
+                    match e {
+                        SecernError::Write { broken_pipe: true, .. } => std::process::exit(0),
+                        e => exit_with_error(e),
                     }
                 }
                 found_match = true;
@@ -173,15 +189,11 @@ fn main() {
         }
 
         if !found_match && !matches.get_flag("no-stdout") {
-            // TODO: Error handling when writing to STDOUT + broken pipe (head -n 10)
-            //       thread 'main' panicked at 'failed printing to stdout: Broken pipe (os error 32)', library/std/src/io/stdio.rs:940:9
-            //       Consider how to close down the various filter files correctly
-            //       https://doc.rust-lang.org/book/ch09-02-recoverable-errors-with-result.html#matching-on-different-errors
-
             // It is faster to use two writes (data followed by \n) than
             // using writeln!()
             match stdio_writer.write_all(line.as_bytes()) {
                 Ok(_) => (),
+                Err(e) if e.kind() == std::io::ErrorKind::BrokenPipe => std::process::exit(0),
                 Err(e) => {
                     error!("Unable to write data to STDOUT due to error: {e}");
                     std::process::exit(1);
@@ -190,6 +202,7 @@ fn main() {
 
             match stdio_writer.write_all(b"\n") {
                 Ok(_) => (),
+                Err(e) if e.kind() == std::io::ErrorKind::BrokenPipe => std::process::exit(0),
                 Err(e) => {
                     error!("Unable to write data to STDOUT due to error: {e}");
                     std::process::exit(1);
@@ -198,7 +211,10 @@ fn main() {
         }
     }
 
-    final_flush(filters, stdio_writer);
+    if let Err(e) = config::final_flush(&mut filters) {
+        exit_with_error(e);
+    }
+    stdio_writer.flush().unwrap();
 
     let duration = start.elapsed();
     info!("Ending data processing. Time elapsed was: {duration:?}");